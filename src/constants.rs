@@ -0,0 +1,42 @@
+//! Tunables for the simulation, gathered in one place so the arena size,
+//! species count, and random-event odds can be adjusted without hunting
+//! through `world.rs`.
+
+pub const WIDTH: isize = 320;
+pub const HEIGHT: isize = 240;
+pub const SPECIES: usize = 4;
+
+/// Frames a freshly (re)born bug tile starts at; see `World::update`.
+pub const MAX_AGE: u8 = 32;
+
+/// Chance a bug tile is skipped entirely on a given update.
+pub const SKIP_CHANCE: f64 = 0.1;
+/// Chance an active bug tile goes dormant instead of spreading.
+pub const DEACTIVE_CHANCE: f64 = 0.05;
+/// Chance a dormant species pulses back to life from its root.
+pub const PULSE_CHANCE: f64 = 0.02;
+
+/// Per-species tile colors, also reused for the HUD population counters.
+pub const COLORS: [[u8; 4]; SPECIES] = [
+    [230, 60, 60, 255],
+    [60, 160, 230, 255],
+    [70, 200, 110, 255],
+    [230, 190, 60, 255],
+];
+
+/// Starting wall probability for `MapGen::Cave`'s random fill.
+pub const CAVE_WALL_CHANCE: f64 = 0.45;
+/// Smoothing passes run over the random fill to connect it into caverns.
+pub const CAVE_SMOOTHING_PASSES: usize = 5;
+
+// Seven-segment HUD sizing and colors; the segment layout tables themselves
+// live in `world.rs` next to the digit renderer that uses them.
+pub const DIGIT_SCALE: isize = 2;
+pub const DIGIT_WIDTH: isize = 5;
+pub const DIGIT_HEIGHT: isize = 9;
+pub const DIGIT_SPACING: isize = 1;
+pub const HUD_PADDING: isize = 2;
+pub const HUD_GROUP_GAP: isize = (DIGIT_WIDTH + DIGIT_SPACING) * DIGIT_SCALE;
+pub const HUD_HEIGHT: isize = DIGIT_HEIGHT * DIGIT_SCALE + HUD_PADDING * 2;
+pub const HUD_BACKGROUND: [u8; 4] = [0, 0, 0, 255];
+pub const FRAME_COUNT_COLOR: [u8; 4] = [200, 200, 200, 255];
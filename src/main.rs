@@ -3,7 +3,7 @@
 
 use error_iter::ErrorIter as _;
 use log::error;
-use pixels::{Error, Pixels, SurfaceTexture};
+use pixels::{PixelsBuilder, SurfaceTexture};
 use winit::dpi::LogicalSize;
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::EventLoop;
@@ -11,14 +11,33 @@ use winit::keyboard::KeyCode;
 use winit::window::WindowBuilder;
 use winit_input_helper::WinitInputHelper;
 
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
 mod constants;
 mod world;
 
 use constants::*;
 use world::*;
 
-fn main() -> Result<(), Error> {
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+const SAVE_PATH: &str = "bug-shine.save";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> anyhow::Result<()> {
     env_logger::init();
+    pollster::block_on(run())
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(start)]
+pub async fn main() -> Result<(), JsValue> {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(log::Level::Warn).expect("could not init console_log");
+    run().await.map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+async fn run() -> anyhow::Result<()> {
     let event_loop = EventLoop::new().unwrap();
     let mut input = WinitInputHelper::new();
     let window = {
@@ -34,9 +53,16 @@ fn main() -> Result<(), Error> {
     let mut pixels = {
         let window_size = window.inner_size();
         let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WIDTH as u32, HEIGHT as u32, surface_texture)?
+        PixelsBuilder::new(WIDTH as u32, HEIGHT as u32, surface_texture)
+            .build_async()
+            .await?
     };
-    let mut world = World::new();
+    let mut map_gen = MapGen::Cave;
+    let mut world = World::new(map_gen);
+
+    let mut paused = false;
+    let mut steps_per_frame: usize = 1;
+    let mut drag_cursor: Option<(usize, usize)> = None;
 
     let res = event_loop.run(|event, elwt| {
         // Draw the current frame
@@ -70,6 +96,42 @@ fn main() -> Result<(), Error> {
                 }
             }
 
+            // Drag-to-paint: left button lays walls (or, held with Shift, seeds
+            // the player's bugs instead), right button erases walls
+            let shift_held =
+                input.key_held(KeyCode::ShiftLeft) || input.key_held(KeyCode::ShiftRight);
+            let paint_kind = if input.mouse_held(0) {
+                if shift_held {
+                    Some(PaintKind::Seed(0))
+                } else {
+                    Some(PaintKind::Wall)
+                }
+            } else if input.mouse_held(1) {
+                Some(PaintKind::Erase)
+            } else {
+                None
+            };
+
+            if let Some(kind) = paint_kind {
+                if let Some(pos) = input.cursor() {
+                    let x = pos.0 as usize;
+                    let y = pos.1 as usize;
+
+                    // Only paint once the hold has lasted more than one frame, so a
+                    // plain click (handled above via `mouse_released`) doesn't also
+                    // drop a zero-length stroke at the click point.
+                    if let Some((x0, y0)) = drag_cursor {
+                        for (px, py) in bresenham_line(x0, y0, x, y) {
+                            world.paint(px, py, kind);
+                        }
+                    }
+
+                    drag_cursor = Some((x, y));
+                }
+            } else {
+                drag_cursor = None;
+            }
+
             // Resize the window
             if let Some(size) = input.window_resized() {
                 if let Err(err) = pixels.resize_surface(size.width, size.height) {
@@ -79,12 +141,74 @@ fn main() -> Result<(), Error> {
                 }
             }
 
-            // Update internal state and request a redraw
-            world.update();
+            // Simulation controls: pause, single-step, fast-forward
+            if input.key_pressed(KeyCode::KeyP) {
+                paused = !paused;
+            }
+            if input.key_pressed(KeyCode::KeyF) {
+                const FAST_FORWARD_STEPS: usize = 5;
+                steps_per_frame = if steps_per_frame == 1 { FAST_FORWARD_STEPS } else { 1 };
+            }
+
+            // Swap the map generator and start a fresh colony on it
+            if input.key_pressed(KeyCode::KeyM) {
+                map_gen = match map_gen {
+                    MapGen::Perlin => MapGen::Cave,
+                    MapGen::Cave => MapGen::Perlin,
+                };
+                world = World::new(map_gen);
+            }
+
+            let mut step_once = false;
+            if input.key_pressed(KeyCode::Space) {
+                paused = true;
+                step_once = true;
+            }
+
+            // Save/load the colony so a run can be resumed or shared
+            let mut just_loaded = false;
+            #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+            {
+                if input.key_pressed(KeyCode::KeyS) {
+                    match world.save_to_bytes() {
+                        Ok(bytes) => {
+                            if let Err(err) = std::fs::write(SAVE_PATH, bytes) {
+                                error!("failed to write {SAVE_PATH}: {err}");
+                            }
+                        }
+                        Err(err) => error!("failed to serialize world: {err}"),
+                    }
+                }
+                if input.key_pressed(KeyCode::KeyL) {
+                    match std::fs::read(SAVE_PATH) {
+                        Ok(bytes) => match World::load_from_bytes(&bytes) {
+                            Ok(loaded) => {
+                                world = loaded;
+                                just_loaded = true;
+                            }
+                            Err(err) => error!("failed to deserialize {SAVE_PATH}: {err}"),
+                        },
+                        Err(err) => error!("failed to read {SAVE_PATH}: {err}"),
+                    }
+                }
+            }
+
+            // Update internal state and request a redraw. Skip stepping and the
+            // game-over check for the frame a save was loaded on, so a loaded
+            // finished game is rendered once instead of being discarded unseen.
+            if just_loaded {
+                // Nothing to do this frame; render the just-loaded state as-is.
+            } else if step_once {
+                world.update();
+            } else if !paused {
+                for _ in 0..steps_per_frame {
+                    world.update();
+                }
+            }
             window.request_redraw();
 
             // TODO: Fix this
-            if world.is_game_over() {
+            if !just_loaded && world.is_game_over() {
                 println!("Game over!");
                 if world.winner().is_some_and(|w| w == 0) {
                     println!("You win!");
@@ -92,11 +216,53 @@ fn main() -> Result<(), Error> {
                     println!("You lose!");
                 }
 
-                world = World::new();
+                world = World::new(map_gen);
             }
         }
     });
-    res.map_err(|e| Error::UserDefined(Box::new(e)))
+    res?;
+    Ok(())
+}
+
+/// Rasterize a straight line from `(x0, y0)` to `(x1, y1)` with Bresenham's
+/// algorithm, so a fast drag still paints every cell along the stroke.
+///
+/// The line is always stepped from the smaller endpoint, so swapping the
+/// endpoints yields the same cells in reverse order.
+fn bresenham_line(x0: usize, y0: usize, x1: usize, y1: usize) -> Vec<(usize, usize)> {
+    if (x1, y1) < (x0, y0) {
+        let mut cells = bresenham_line(x1, y1, x0, y0);
+        cells.reverse();
+        return cells;
+    }
+
+    let (mut x0, mut y0, x1, y1) = (x0 as isize, y0 as isize, x1 as isize, y1 as isize);
+
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut cells = Vec::new();
+    loop {
+        cells.push((x0 as usize, y0 as usize));
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y0 += sy;
+        }
+    }
+
+    cells
 }
 
 fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
@@ -105,3 +271,45 @@ fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
         error!("  Caused by: {source}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn horizontal_line() {
+        assert_eq!(
+            bresenham_line(1, 5, 4, 5),
+            vec![(1, 5), (2, 5), (3, 5), (4, 5)]
+        );
+    }
+
+    #[test]
+    fn vertical_line() {
+        assert_eq!(
+            bresenham_line(5, 1, 5, 4),
+            vec![(5, 1), (5, 2), (5, 3), (5, 4)]
+        );
+    }
+
+    #[test]
+    fn diagonal_line() {
+        assert_eq!(
+            bresenham_line(0, 0, 3, 3),
+            vec![(0, 0), (1, 1), (2, 2), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn reverse_direction_matches_forward() {
+        let forward = bresenham_line(0, 0, 4, 2);
+        let mut backward = bresenham_line(4, 2, 0, 0);
+        backward.reverse();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    fn single_point() {
+        assert_eq!(bresenham_line(2, 2, 2, 2), vec![(2, 2)]);
+    }
+}
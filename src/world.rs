@@ -1,10 +1,16 @@
 use noise::NoiseFn;
-use rand::{seq::SliceRandom, Rng};
+use rand::rngs::StdRng;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::constants::*;
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct World {
-    random: rand::rngs::ThreadRng,
+    #[cfg_attr(feature = "serde", serde(skip, default = "World::fresh_rng"))]
+    random: StdRng,
+    seed: u64,
     tiles: [[Tile; WIDTH as usize]; HEIGHT as usize],
     active: Vec<(usize, usize)>,
     winner: Option<u8>,
@@ -12,6 +18,7 @@ pub struct World {
     frame_count: usize,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Tile {
     Empty,
@@ -19,30 +26,61 @@ enum Tile {
     Bug(u8, u8),
 }
 
+/// What a drag stroke paints a tile as; see [`World::paint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaintKind {
+    Wall,
+    Erase,
+    /// Drop a fresh bug of the given species onto an empty tile.
+    Seed(u8),
+}
+
+/// How `World::new` lays out the starting walls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapGen {
+    /// A single Perlin noise threshold; blobby and unnatural but cheap.
+    Perlin,
+    /// A cellular-automata cave: random fill, smoothed into connected caverns.
+    Cave,
+}
+
 impl World {
     /// Create a new `World` instance that can draw a moving box.
-    pub fn new() -> Self {
-        let mut random = rand::thread_rng();
+    pub fn new(map_gen: MapGen) -> Self {
+        let seed = rand::thread_rng().gen();
+        Self::from_seed(map_gen, seed)
+    }
+
+    /// Create a `World` from an explicit seed, so a run can be reproduced later.
+    pub fn from_seed(map_gen: MapGen, seed: u64) -> Self {
+        let mut random = StdRng::seed_from_u64(seed);
         let mut tiles = [[Tile::Empty; WIDTH as usize]; HEIGHT as usize];
         let mut active = Vec::new();
         let mut roots = [(0, 0); SPECIES];
 
-        // Start with some NOISE
-        let perlin = noise::Perlin::new(random.gen_range(0..1000));
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                let value = perlin.get([x as f64 / 100.0, y as f64 / 100.0, 0.0]);
-                if value > 0.25 {
-                    tiles[y as usize][x as usize] = Tile::Wall;
+        match map_gen {
+            MapGen::Perlin => {
+                // Start with some NOISE
+                let perlin = noise::Perlin::new(random.gen_range(0..1000));
+                for x in 0..WIDTH {
+                    for y in 0..HEIGHT {
+                        let value = perlin.get([x as f64 / 100.0, y as f64 / 100.0, 0.0]);
+                        if value > 0.25 {
+                            tiles[y as usize][x as usize] = Tile::Wall;
+                        }
+                    }
                 }
             }
+            MapGen::Cave => {
+                generate_cave(&mut tiles, &mut random);
+            }
         }
 
         // Add some bugs, can't be on walls
         for id in 0..SPECIES {
             loop {
-                let x = (WIDTH as f32 * rand::random::<f32>()) as usize;
-                let y = (HEIGHT as f32 * rand::random::<f32>()) as usize;
+                let x = (WIDTH as f32 * random.gen::<f32>()) as usize;
+                let y = (HEIGHT as f32 * random.gen::<f32>()) as usize;
 
                 if tiles[y][x] != Tile::Empty {
                     continue;
@@ -58,6 +96,7 @@ impl World {
 
         Self {
             random,
+            seed,
             tiles,
             active,
             winner: None,
@@ -66,6 +105,13 @@ impl World {
         }
     }
 
+    /// Placeholder RNG used only to satisfy `serde(default)` while deserializing;
+    /// `load_from_bytes` immediately reseeds it from the persisted `seed`.
+    #[cfg(feature = "serde")]
+    fn fresh_rng() -> StdRng {
+        StdRng::seed_from_u64(0)
+    }
+
     pub fn is_game_over(&self) -> bool {
         self.winner.is_some()
     }
@@ -161,8 +207,8 @@ impl World {
 
                     // Root was taken over, find another one
                     for _ in 0..100 {
-                        let x = (WIDTH as f32 * rand::random::<f32>()) as usize;
-                        let y = (HEIGHT as f32 * rand::random::<f32>()) as usize;
+                        let x = (WIDTH as f32 * self.random.gen::<f32>()) as usize;
+                        let y = (HEIGHT as f32 * self.random.gen::<f32>()) as usize;
 
                         if let Tile::Bug(root_id, _) = self.tiles[y][x] {
                             if id == root_id as usize {
@@ -206,9 +252,43 @@ impl World {
 
             pixel.copy_from_slice(&rgba);
         }
+
+        self.draw_hud(frame);
+    }
+
+    /// Draw the HUD strip: live population per species in its own color,
+    /// followed by the frame count, composed over the tiles already drawn.
+    fn draw_hud(&self, frame: &mut [u8]) {
+        let mut counts = [0usize; SPECIES];
+        for row in self.tiles.iter() {
+            for tile in row.iter() {
+                if let Tile::Bug(id, _) = tile {
+                    counts[*id as usize] += 1;
+                }
+            }
+        }
+
+        // Solid backdrop so digits stay legible over whatever tiles sit underneath
+        for y in 0..HUD_HEIGHT {
+            for x in 0..WIDTH {
+                set_pixel(frame, x, y, HUD_BACKGROUND);
+            }
+        }
+
+        let mut x = HUD_PADDING;
+        for (id, count) in counts.iter().enumerate() {
+            x = draw_number(frame, x, HUD_PADDING, *count, COLORS[id]);
+            x += HUD_GROUP_GAP;
+        }
+
+        draw_number(frame, x, HUD_PADDING, self.frame_count, FRAME_COUNT_COLOR);
     }
 
     pub fn click(&mut self, x: usize, y: usize) {
+        if x >= WIDTH as usize || y >= HEIGHT as usize {
+            return;
+        }
+
         // Move our root to the clicked location
         match self.tiles[y][x] {
             Tile::Bug(clicked_id, _) if clicked_id == 0 => {
@@ -219,4 +299,217 @@ impl World {
             _ => {}
         };
     }
+
+    /// Paint a single tile while dragging: lay a wall, erase one back to empty,
+    /// or seed a new bug. Never overwrites an existing bug, so a drag can't be
+    /// used to snipe a species out directly or to paint over another's root.
+    pub fn paint(&mut self, x: usize, y: usize, kind: PaintKind) {
+        if x >= WIDTH as usize || y >= HEIGHT as usize {
+            return;
+        }
+
+        match kind {
+            PaintKind::Wall => {
+                if self.tiles[y][x] == Tile::Empty {
+                    self.tiles[y][x] = Tile::Wall;
+                }
+            }
+            PaintKind::Erase => {
+                if self.tiles[y][x] == Tile::Wall {
+                    self.tiles[y][x] = Tile::Empty;
+                }
+            }
+            PaintKind::Seed(id) => {
+                if self.tiles[y][x] == Tile::Empty {
+                    self.tiles[y][x] = Tile::Bug(id, MAX_AGE);
+                    self.active.push((x, y));
+                }
+            }
+        }
+    }
+
+    /// Snapshot the world to a compact binary blob for saving to disk.
+    ///
+    /// The format is schema-less postcard, so it's only compatible with
+    /// loads from a build with the same `WIDTH`/`HEIGHT`/`SPECIES` constants:
+    /// `tiles` is a fixed-size array, and a mismatch will make
+    /// [`World::load_from_bytes`] fail to deserialize or panic on bounds
+    /// rather than reporting a clean error.
+    #[cfg(feature = "serde")]
+    pub fn save_to_bytes(&self) -> postcard::Result<Vec<u8>> {
+        postcard::to_allocvec(self)
+    }
+
+    /// Restore a world previously written by [`World::save_to_bytes`].
+    ///
+    /// The RNG itself isn't persisted (only its seed is), so this reseeds
+    /// it rather than trying to serialize `StdRng`'s internal state. See
+    /// [`World::save_to_bytes`] for the format's compatibility caveats.
+    #[cfg(feature = "serde")]
+    pub fn load_from_bytes(bytes: &[u8]) -> postcard::Result<Self> {
+        let mut world: Self = postcard::from_bytes(bytes)?;
+        world.random = StdRng::seed_from_u64(world.seed);
+        Ok(world)
+    }
+}
+
+// Seven-segment HUD digits: each digit lights a subset of segments A..G
+// (top, top-right, bottom-right, bottom, bottom-left, top-left, middle).
+// The sizing/color tunables live in `constants`; only the segment layout
+// tables, which are data rather than knobs, stay here.
+
+/// Unscaled (x, y, w, h) rectangle for each segment, in A, B, C, D, E, F, G order.
+const SEGMENT_RECTS: [(isize, isize, isize, isize); 7] = [
+    (1, 0, 3, 1), // A: top
+    (4, 1, 1, 3), // B: top-right
+    (4, 5, 1, 3), // C: bottom-right
+    (1, 8, 3, 1), // D: bottom
+    (0, 5, 1, 3), // E: bottom-left
+    (0, 1, 1, 3), // F: top-left
+    (1, 4, 3, 1), // G: middle
+];
+
+/// Which segments are lit for each digit 0..9, in A, B, C, D, E, F, G order.
+const DIGIT_SEGMENTS: [[bool; 7]; 10] = [
+    [true, true, true, true, true, true, false],     // 0
+    [false, true, true, false, false, false, false], // 1
+    [true, true, false, true, true, false, true],    // 2
+    [true, true, true, true, false, false, true],    // 3
+    [false, true, true, false, false, true, true],   // 4
+    [true, false, true, true, false, true, true],    // 5
+    [true, false, true, true, true, true, true],     // 6
+    [true, true, true, false, false, false, false],  // 7
+    [true, true, true, true, true, true, true],      // 8
+    [true, true, true, true, false, true, true],     // 9
+];
+
+/// Write a single RGBA pixel, silently clipping anything off the frame buffer.
+fn set_pixel(frame: &mut [u8], x: isize, y: isize, rgba: [u8; 4]) {
+    if x < 0 || y < 0 || x >= WIDTH || y >= HEIGHT {
+        return;
+    }
+
+    let i = (y as usize * WIDTH as usize + x as usize) * 4;
+    frame[i..i + 4].copy_from_slice(&rgba);
+}
+
+/// Draw one seven-segment digit with its top-left corner at `(x0, y0)`.
+fn draw_digit(frame: &mut [u8], x0: isize, y0: isize, digit: u8, color: [u8; 4]) {
+    for (&lit, &(sx, sy, sw, sh)) in DIGIT_SEGMENTS[digit as usize].iter().zip(&SEGMENT_RECTS) {
+        if !lit {
+            continue;
+        }
+
+        for dy in 0..sh * DIGIT_SCALE {
+            for dx in 0..sw * DIGIT_SCALE {
+                set_pixel(frame, x0 + sx * DIGIT_SCALE + dx, y0 + sy * DIGIT_SCALE + dy, color);
+            }
+        }
+    }
+}
+
+/// Draw `value` as a run of seven-segment digits starting at `(x0, y0)`,
+/// returning the x coordinate just past the last digit drawn.
+fn draw_number(frame: &mut [u8], x0: isize, y0: isize, value: usize, color: [u8; 4]) -> isize {
+    let mut digits = Vec::new();
+    let mut remaining = value;
+    loop {
+        digits.push((remaining % 10) as u8);
+        remaining /= 10;
+        if remaining == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+
+    let mut x = x0;
+    for digit in digits {
+        draw_digit(frame, x, y0, digit, color);
+        x += (DIGIT_WIDTH + DIGIT_SPACING) * DIGIT_SCALE;
+    }
+
+    x
+}
+
+/// Fill `tiles` with a cellular-automata cave: random noise smoothed by
+/// repeated Moore-neighborhood majority passes into connected caverns, with
+/// the outermost ring of tiles then set to walls so the border is closed.
+fn generate_cave(tiles: &mut [[Tile; WIDTH as usize]; HEIGHT as usize], random: &mut StdRng) {
+    for row in tiles.iter_mut() {
+        for tile in row.iter_mut() {
+            *tile = if random.gen_range(0.0..1.0) < CAVE_WALL_CHANCE {
+                Tile::Wall
+            } else {
+                Tile::Empty
+            };
+        }
+    }
+
+    for _ in 0..CAVE_SMOOTHING_PASSES {
+        let snapshot = *tiles;
+
+        for y in 0..HEIGHT as isize {
+            for x in 0..WIDTH as isize {
+                let mut wall_neighbors = 0;
+                for dy in -1..2 {
+                    for dx in -1..2 {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+
+                        let nx = x + dx;
+                        let ny = y + dy;
+
+                        let is_wall = if nx < 0 || ny < 0 || nx >= WIDTH || ny >= HEIGHT {
+                            true
+                        } else {
+                            snapshot[ny as usize][nx as usize] == Tile::Wall
+                        };
+
+                        if is_wall {
+                            wall_neighbors += 1;
+                        }
+                    }
+                }
+
+                tiles[y as usize][x as usize] = if wall_neighbors >= 5 || wall_neighbors == 0 {
+                    Tile::Wall
+                } else {
+                    Tile::Empty
+                };
+            }
+        }
+    }
+
+    // Counting out-of-bounds as walls still leaves gaps along the edge, so seal it
+    for x in 0..WIDTH as usize {
+        tiles[0][x] = Tile::Wall;
+        tiles[HEIGHT as usize - 1][x] = Tile::Wall;
+    }
+    for row in tiles.iter_mut() {
+        row[0] = Tile::Wall;
+        row[WIDTH as usize - 1] = Tile::Wall;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// After generation every border tile is a wall.
+    #[test]
+    fn generate_cave_seals_the_border() {
+        let mut tiles = [[Tile::Empty; WIDTH as usize]; HEIGHT as usize];
+        let mut random = StdRng::seed_from_u64(42);
+        generate_cave(&mut tiles, &mut random);
+
+        for x in 0..WIDTH as usize {
+            assert_eq!(tiles[0][x], Tile::Wall, "top border at x={x}");
+            assert_eq!(tiles[HEIGHT as usize - 1][x], Tile::Wall, "bottom border at x={x}");
+        }
+        for y in 0..HEIGHT as usize {
+            assert_eq!(tiles[y][0], Tile::Wall, "left border at y={y}");
+            assert_eq!(tiles[y][WIDTH as usize - 1], Tile::Wall, "right border at y={y}");
+        }
+    }
 }